@@ -0,0 +1,10 @@
+use std::path::PathBuf;
+
+/// Runtime configuration for Niobium, as loaded from the application's config file.
+#[allow(non_snake_case)]
+pub struct Config {
+    /// Path to the sqlite database file used to store the photos information.
+    pub DATABASE_PATH: PathBuf,
+    /// Number of connections to keep open in the database connection pool.
+    pub DATABASE_POOL_SIZE: u32,
+}
@@ -0,0 +1,41 @@
+//! Online, page-by-page snapshots of the live photos database.
+//!
+//! Copying the database file directly while WAL is active can produce a corrupt copy, so this
+//! uses rusqlite's `backup` feature (SQLite's online backup API) to hot-copy a live connection
+//! into a destination database a handful of pages at a time. `Backup::run_to_completion` already
+//! retries on `SQLITE_BUSY`/`SQLITE_LOCKED`, sleeping between steps, so normal reads against the
+//! source database are never blocked for the whole duration of the backup.
+
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::backup::{Backup, Progress};
+use rusqlite::Connection;
+
+use crate::Error;
+
+use super::DatabaseConnectionPool;
+
+/// How many pages to copy per step before pausing, and how long to pause for.
+const PAGES_PER_STEP: i32 = 100;
+const PAUSE_BETWEEN_STEPS: Duration = Duration::from_millis(250);
+
+/// Snapshot the live photos database into a new database file at `dest`.
+///
+/// Runs on a connection checked out of the pool, inside `tokio::task::spawn_blocking`, so the
+/// (potentially long-running) backup never blocks the async runtime. `on_progress`, if given, is
+/// called after each step with the number of pages remaining and the total page count.
+pub async fn backup_to(db_pool: &DatabaseConnectionPool, dest: &Path, on_progress: Option<fn(Progress)>) -> Result<(), Error> {
+    let db_pool = db_pool.clone();
+    let dest = dest.to_path_buf();
+
+    tokio::task::spawn_blocking(move || {
+        let src_conn = db_pool.get().map_err(|e| Error::DatabasePoolError(e))?;
+        let mut dst_conn = Connection::open(&dest).map_err(|e| Error::DatabaseError(e))?;
+
+        let backup = Backup::new(&src_conn, &mut dst_conn).map_err(|e| Error::DatabaseError(e))?;
+
+        backup.run_to_completion(PAGES_PER_STEP, PAUSE_BETWEEN_STEPS, on_progress)
+            .map_err(|e| Error::DatabaseError(e))
+    }).await.unwrap()
+}
@@ -0,0 +1,126 @@
+//! Application-defined scalar SQL functions for EXIF-based filtering.
+//!
+//! `register` is called once per connection, from `pool::ConnectionCustomizer::on_acquire`, since
+//! a registered function lives for the lifetime of the connection it was registered on.
+
+use rusqlite::functions::FunctionFlags;
+use rusqlite::Connection;
+
+/// Register every application-defined scalar function on `db_conn`.
+pub fn register(db_conn: &Connection) -> rusqlite::Result<()> {
+    db_conn.create_scalar_function(
+        "color_distance",
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let a = ctx.get::<Option<String>>(0)?;
+            let b = ctx.get::<Option<String>>(1)?;
+            Ok(color_distance(a.as_deref(), b.as_deref()))
+        },
+    )?;
+
+    db_conn.create_scalar_function(
+        "within_days",
+        3,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let date_taken = ctx.get::<Option<String>>(0)?;
+            let target = ctx.get::<String>(1)?;
+            let days = ctx.get::<i64>(2)?;
+            Ok(within_days(date_taken.as_deref(), &target, days))
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Euclidean distance in RGB space between two `#rrggbb` colors, for `ORDER BY` use in
+/// "similar dominant color" queries. A NULL or unparseable color is treated as maximally distant
+/// from everything, so it sorts last rather than erroring out the whole query.
+fn color_distance(a: Option<&str>, b: Option<&str>) -> f64 {
+    match (a.and_then(parse_hex_color), b.and_then(parse_hex_color)) {
+        (Some([a_r, a_g, a_b]), Some([b_r, b_g, b_b])) => {
+            let d_r = a_r as f64 - b_r as f64;
+            let d_g = a_g as f64 - b_g as f64;
+            let d_b = a_b as f64 - b_b as f64;
+            (d_r * d_r + d_g * d_g + d_b * d_b).sqrt()
+        }
+        _ => f64::MAX,
+    }
+}
+
+fn parse_hex_color(color: &str) -> Option<[u8; 3]> {
+    let color = color.trim_start_matches('#');
+    if color.len() != 6 || !color.is_ascii() {
+        return None;
+    }
+
+    Some([
+        u8::from_str_radix(&color[0..2], 16).ok()?,
+        u8::from_str_radix(&color[2..4], 16).ok()?,
+        u8::from_str_radix(&color[4..6], 16).ok()?,
+    ])
+}
+
+/// Whether `date_taken` falls within `days` days of `target`, for flexible date-window queries.
+/// Both dates are expected to start with a `YYYY-MM-DD` prefix; anything else never matches.
+fn within_days(date_taken: Option<&str>, target: &str, days: i64) -> bool {
+    match (date_taken.and_then(parse_date), parse_date(target)) {
+        (Some(date_taken), Some(target)) => (date_taken - target).num_days().abs() <= days,
+        _ => false,
+    }
+}
+
+fn parse_date(value: &str) -> Option<chrono::NaiveDate> {
+    value.get(0..10).and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_distance_is_zero_for_identical_colors() {
+        assert_eq!(color_distance(Some("#ff0000"), Some("#ff0000")), 0.0);
+    }
+
+    #[test]
+    fn color_distance_computes_euclidean_distance_in_rgb_space() {
+        let distance = color_distance(Some("#000000"), Some("#ffffff"));
+        assert!((distance - (3.0_f64 * 255.0 * 255.0).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn color_distance_treats_a_missing_color_as_maximally_distant() {
+        assert_eq!(color_distance(None, Some("#ffffff")), f64::MAX);
+        assert_eq!(color_distance(Some("#ffffff"), None), f64::MAX);
+    }
+
+    #[test]
+    fn color_distance_treats_an_unparseable_color_as_maximally_distant() {
+        assert_eq!(color_distance(Some("not-a-color"), Some("#ffffff")), f64::MAX);
+    }
+
+    #[test]
+    fn parse_hex_color_accepts_an_optional_leading_hash() {
+        assert_eq!(parse_hex_color("#112233"), Some([0x11, 0x22, 0x33]));
+        assert_eq!(parse_hex_color("112233"), Some([0x11, 0x22, 0x33]));
+    }
+
+    #[test]
+    fn parse_hex_color_rejects_the_wrong_length() {
+        assert_eq!(parse_hex_color("#123"), None);
+    }
+
+    #[test]
+    fn within_days_matches_at_the_boundary() {
+        assert!(within_days(Some("2024-01-01"), "2024-01-04", 3));
+        assert!(!within_days(Some("2024-01-01"), "2024-01-05", 3));
+    }
+
+    #[test]
+    fn within_days_rejects_unparseable_dates() {
+        assert!(!within_days(None, "2024-01-01", 3));
+        assert!(!within_days(Some("not-a-date"), "2024-01-01", 3));
+    }
+}
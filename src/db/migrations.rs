@@ -0,0 +1,95 @@
+//! Versioned schema migrations for the photos database.
+//!
+//! Each migration is a plain SQL script, embedded in the binary so deployments don't depend on
+//! an external `schema.sql` file. The database's applied version is tracked with SQLite's
+//! built-in `PRAGMA user_version`; on startup, every migration whose index is greater than the
+//! current version is run, in order, inside a single transaction per migration. Migrations are
+//! append-only: never reorder or remove an entry, only add new ones at the end.
+
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::Error;
+
+/// A single schema migration step.
+struct Migration {
+    /// Short human-readable description, printed while migrations are applied.
+    description: &'static str,
+    /// The SQL script that brings the schema from the previous version to this one.
+    up: &'static str,
+}
+
+/// All known migrations, in application order. The database's `user_version` after migrating is
+/// the length of this slice.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "initial schema",
+        up: include_str!("migrations/0001_initial_schema.sql"),
+    },
+    Migration {
+        description: "full-text search over photo metadata",
+        up: include_str!("migrations/0002_fts5_search.sql"),
+    },
+];
+
+/// Bring `db_conn`'s schema up to date by applying every migration newer than its current
+/// `user_version`.
+///
+/// If the database's `user_version` is already greater than the number of known migrations, it
+/// was created by a newer version of Niobium; refuse to start rather than risk silently
+/// operating on a schema we don't understand.
+pub fn run(db_conn: &Connection) -> Result<(), Error> {
+    let mut current_version: u32 = db_conn.query_row("PRAGMA user_version;", [], |row| row.get(0))
+        .map_err(|e| Error::DatabaseError(e))?;
+
+    let target_version = MIGRATIONS.len() as u32;
+
+    if current_version > target_version {
+        eprintln!(
+            "Error, the database schema (version {}) is newer than the versions known to this build of Niobium (version {}). Refusing to start.",
+            current_version, target_version
+        );
+        std::process::exit(-1);
+    }
+
+    // Databases created by the old one-shot `schema.sql` bootstrap already have the `photo` table
+    // but never had `user_version` set, so they report version 0 just like a brand new database.
+    // Detect that case and skip straight past the "initial schema" migration instead of
+    // re-running its `CREATE TABLE`, which would fail against the table that already exists.
+    if current_version == 0 && photo_table_exists(db_conn)? {
+        db_conn.pragma_update(None, "user_version", 1u32)
+            .map_err(|e| Error::DatabaseError(e))?;
+        current_version = 1;
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(current_version as usize) {
+        let new_version = (index + 1) as u32;
+        print!("Applying database migration {} ({})... ", new_version, migration.description);
+
+        let tx = db_conn.unchecked_transaction()
+            .map_err(|e| Error::DatabaseError(e))?;
+
+        tx.execute_batch(migration.up)
+            .map_err(|e| Error::DatabaseError(e))?;
+
+        tx.pragma_update(None, "user_version", new_version)
+            .map_err(|e| Error::DatabaseError(e))?;
+
+        tx.commit().map_err(|e| Error::DatabaseError(e))?;
+
+        println!("ok");
+    }
+
+    Ok(())
+}
+
+/// Whether the `photo` table already exists, i.e. the database was bootstrapped by the old
+/// one-shot `schema.sql` import rather than by this migration engine.
+fn photo_table_exists(db_conn: &Connection) -> Result<bool, Error> {
+    db_conn.query_row(
+        "SELECT name FROM sqlite_master WHERE type='table' AND name='photo';",
+        [], |row| row.get::<_, String>(0)
+    )
+        .optional()
+        .map(|result| result.is_some())
+        .map_err(|e| Error::DatabaseError(e))
+}
@@ -0,0 +1,313 @@
+use std::path::PathBuf;
+
+use crate::{config::Config, Error, photos::Photo};
+use rusqlite::Row;
+use serde_rusqlite::{from_row, to_params_named};
+
+mod backup;
+mod functions;
+mod migrations;
+mod pool;
+mod search;
+
+pub mod path_as_text;
+
+pub use backup::backup_to;
+pub use pool::DatabaseConnectionPool;
+pub use search::search_photos;
+
+
+/// Open the connection pool used to access the photos database, applying every migration needed
+/// to bring it up to date (creating it from scratch first if it doesn't exist).
+/// In case of error, print it to stderr and exit with a status code of -1
+pub fn open_or_exit(config: &Config) -> DatabaseConnectionPool {
+    let db_pool = pool::build_pool(config).unwrap_or_else(|error| {
+        eprintln!("Error, unable to open the database : {}", error);
+        std::process::exit(-1);
+    });
+
+    let db_conn = db_pool.get().unwrap_or_else(|error| {
+        eprintln!("Error, unable to check out a database connection : {}", error);
+        std::process::exit(-1);
+    });
+
+    migrations::run(&db_conn).unwrap_or_else(|error| {
+        eprintln!("Error, unable to migrate the database : {}", error);
+        std::process::exit(-1);
+    });
+
+    db_pool
+}
+
+
+/// Get the list of UIDs that exist in the database
+pub async fn get_existing_uids(db_pool: &DatabaseConnectionPool) -> Result<Vec<String>, Error> {
+    let db_pool = db_pool.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_conn = db_pool.get().map_err(|e| Error::DatabasePoolError(e))?;
+
+        let sql = "SELECT uid FROM photo;";
+
+        let mut stmt = db_conn.prepare(sql)
+            .map_err(|e| Error::DatabaseError(e))?;
+
+        let uids = stmt.query_map([], |row| row.get(0))
+            .map_err(|e| Error::DatabaseError(e))?
+            .map(|x| x.unwrap())
+            .collect::<Vec<String>>();
+
+        Ok(uids)
+    }).await.unwrap()
+}
+
+
+/// Get the list of unique paths known in the database that start with the given path
+pub async fn get_paths_starting_with(db_pool: &DatabaseConnectionPool, path: &PathBuf) -> Result<Vec<PathBuf>, Error> {
+    let db_pool = db_pool.clone();
+    let path = path.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_conn = db_pool.get().map_err(|e| Error::DatabasePoolError(e))?;
+
+        let sql = "SELECT path FROM photo WHERE SUBSTR(path, 1, ?)=? GROUP BY path;";
+
+        let mut stmt = db_conn.prepare(sql)
+            .map_err(|e| Error::DatabaseError(e))?;
+
+        let params = (path.to_str().unwrap().chars().count(), path.to_str().unwrap());
+
+        let paths = stmt.query_map(params, |row|
+            Ok(PathBuf::from(row.get::<usize, String>(0)?))
+        )
+            .map_err(|e| Error::DatabaseError(e))?
+            .map(|x| x.unwrap())
+            .collect::<Vec<PathBuf>>();
+
+        Ok(paths)
+    }).await.unwrap()
+}
+
+
+/// Get the list of photos known in the database that are registered in one of the given paths
+pub async fn get_photos_in_paths(db_pool: &DatabaseConnectionPool, paths: &Vec<PathBuf>) -> Result<Vec<Photo>, Error> {
+    let db_pool = db_pool.clone();
+    let paths = paths.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_conn = db_pool.get().map_err(|e| Error::DatabasePoolError(e))?;
+
+        let mut sql = "SELECT * FROM photo WHERE path IN (".to_string();
+        for (i, _) in paths.iter().enumerate() {
+            if i > 0 {
+                sql += ",";
+            }
+            sql += "?";
+        }
+        sql += ");";
+
+        let mut stmt = db_conn.prepare(sql.as_str())
+            .map_err(|e| Error::DatabaseError(e))?;
+
+        let params = rusqlite::params_from_iter(paths.iter().map(|p| p.to_str().unwrap()));
+
+        let photos = stmt.query_map(params, |row|
+            row_to_photo(row)
+        )
+            .map_err(|e| Error::DatabaseError(e))?
+            .map(|x| x.unwrap())
+            .collect::<Vec<Photo>>();
+
+        Ok(photos)
+    }).await.unwrap()
+}
+
+
+/// Get the list of photos known in the database that are registered in the given path, ordered
+pub async fn get_photos_in_path(db_pool: &DatabaseConnectionPool, path: &PathBuf, sort_columns: &Vec<String>, reverse_sort_order: bool) -> Result<Vec<Photo>, Error> {
+    let db_pool = db_pool.clone();
+    let path = path.clone();
+    let sort_columns = sort_columns.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_conn = db_pool.get().map_err(|e| Error::DatabasePoolError(e))?;
+
+        let mut sql = "SELECT * FROM photo WHERE path=? ORDER BY ".to_string();
+        sql += sort_columns.iter()
+            .map(|clause| clause.clone() + if reverse_sort_order { " DESC" } else { " ASC"})
+            .collect::<Vec<String>>()
+            .join(", ")
+            .as_str();
+        sql += ";";
+
+        let mut stmt = db_conn.prepare(sql.as_str())
+            .map_err(|e| Error::DatabaseError(e))?;
+
+        let params = rusqlite::params![&path.to_str().unwrap()];
+
+        let photos = stmt.query_map(params, |row|
+            row_to_photo(row)
+        )
+            .map_err(|e| Error::DatabaseError(e))?
+            .map(|x| x.unwrap())
+            .collect::<Vec<Photo>>();
+
+        Ok(photos)
+    }).await.unwrap()
+}
+
+
+/// Get up to `limit` photos, ordered by how close their dominant `color` is to `color`, using the
+/// `color_distance` SQL function registered on every connection (see the `functions` module).
+pub async fn get_photos_similar_color(db_pool: &DatabaseConnectionPool, color: &str, limit: usize) -> Result<Vec<Photo>, Error> {
+    let db_pool = db_pool.clone();
+    let color = color.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let db_conn = db_pool.get().map_err(|e| Error::DatabasePoolError(e))?;
+
+        let sql = "SELECT * FROM photo ORDER BY color_distance(color, ?) ASC LIMIT ?;";
+
+        let mut stmt = db_conn.prepare(sql)
+            .map_err(|e| Error::DatabaseError(e))?;
+
+        let photos = stmt.query_map(rusqlite::params![&color, limit as i64], |row|
+            row_to_photo(row)
+        )
+            .map_err(|e| Error::DatabaseError(e))?
+            .map(|x| x.unwrap())
+            .collect::<Vec<Photo>>();
+
+        Ok(photos)
+    }).await.unwrap()
+}
+
+
+/// Insert a list of photos into the database
+///
+/// The column list and bound parameters are derived from `Photo`'s own fields (by name, via
+/// `serde_rusqlite`) rather than a hard-coded `INSERT INTO photo(...)` string, so adding a column
+/// to `Photo` doesn't also require updating this function. The autoincremented `id` column is
+/// always left for SQLite to assign.
+pub async fn insert_photos(db_pool: &DatabaseConnectionPool, photos: &Vec<Photo>) -> Result<(), Error> {
+    let db_pool = db_pool.clone();
+    let photos = photos.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_conn = db_pool.get().map_err(|e| Error::DatabasePoolError(e))?;
+
+        for photo in &photos {
+            let named_params = to_params_named(photo).map_err(|e| Error::DeserializeError(e))?;
+            let params: Vec<(&str, &dyn rusqlite::ToSql)> = named_params.to_slice().into_iter()
+                .filter(|(name, _)| *name != ":id")
+                .collect();
+
+            let columns = params.iter().map(|(name, _)| &name[1..]).collect::<Vec<&str>>().join(", ");
+            let placeholders = params.iter().map(|(name, _)| *name).collect::<Vec<&str>>().join(", ");
+            let sql = format!("INSERT INTO photo ({}) VALUES ({});", columns, placeholders);
+
+            db_conn.execute(sql.as_str(), params.as_slice())
+                .map_err(|e| Error::DatabaseError(e))?;
+        }
+
+        Ok(())
+    }).await.unwrap()
+}
+
+
+/// Remove a list of photos from the database, based on their UIDs
+pub async fn remove_photos(db_pool: &DatabaseConnectionPool, photos: &Vec<Photo>) -> Result<(), Error> {
+    let db_pool = db_pool.clone();
+    let photos = photos.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_conn = db_pool.get().map_err(|e| Error::DatabasePoolError(e))?;
+
+        let sql = "DELETE FROM photo WHERE uid=?;";
+
+        let mut stmt = db_conn.prepare(sql)
+            .map_err(|e| Error::DatabaseError(e))?;
+
+        for photo in &photos {
+            stmt.execute(rusqlite::params![&photo.uid])
+            .map_err(|e| Error::DatabaseError(e))?;
+        }
+
+        stmt.finalize().map_err(|e| Error::DatabaseError(e))
+    }).await.unwrap()
+}
+
+
+/// Rename/move a list of photos in the database, based on their UIDs
+pub async fn move_photos(db_pool: &DatabaseConnectionPool, photos_pairs: &Vec<(Photo, Photo)>) -> Result<(), Error> {
+    let db_pool = db_pool.clone();
+    let photos_pairs = photos_pairs.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let db_conn = db_pool.get().map_err(|e| Error::DatabasePoolError(e))?;
+
+        let sql = "UPDATE photo SET filename=?, path=? WHERE uid=?;";
+
+        let mut stmt = db_conn.prepare(sql)
+            .map_err(|e| Error::DatabaseError(e))?;
+
+        for photos_pair in &photos_pairs {
+            stmt.execute(rusqlite::params![&photos_pair.1.filename, &photos_pair.1.path.to_str().unwrap(), &photos_pair.0.uid])
+            .map_err(|e| Error::DatabaseError(e))?;
+        }
+
+        stmt.finalize().map_err(|e| Error::DatabaseError(e))
+    }).await.unwrap()
+}
+
+
+/// Deserialize an SQL row into a Photo struct by column name, via `serde_rusqlite`, so a
+/// `SELECT *` column order no longer has to match `Photo`'s field order.
+fn row_to_photo(row: &Row) -> rusqlite::Result<Photo> {
+    from_row::<Photo>(row).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use r2d2_sqlite::SqliteConnectionManager;
+
+    /// A pool backed by a single in-memory connection, with migrations already applied.
+    fn test_pool() -> DatabaseConnectionPool {
+        let db_pool = r2d2::Pool::builder()
+            .max_size(1)
+            .build(SqliteConnectionManager::memory())
+            .unwrap();
+
+        migrations::run(&db_pool.get().unwrap()).unwrap();
+
+        db_pool
+    }
+
+    #[tokio::test]
+    async fn insert_photos_round_trips_through_get_photos_in_path() {
+        let db_pool = test_pool();
+
+        let photo = Photo {
+            filename: "IMG_0001.jpg".to_string(),
+            path: PathBuf::from("/photos/2024"),
+            uid: "abc123".to_string(),
+            md5: "d41d8cd98f00b204e9800998ecf8427e".to_string(),
+            title: Some("Sunset".to_string()),
+            camera_model: Some("Canon EOS R5".to_string()),
+            ..Default::default()
+        };
+
+        insert_photos(&db_pool, &vec![photo.clone()]).await.unwrap();
+
+        let photos = get_photos_in_path(&db_pool, &photo.path, &vec!["id".to_string()], false).await.unwrap();
+
+        assert_eq!(photos.len(), 1);
+        assert_eq!(photos[0].filename, photo.filename);
+        assert_eq!(photos[0].path, photo.path);
+        assert_eq!(photos[0].uid, photo.uid);
+        assert_eq!(photos[0].md5, photo.md5);
+        assert_eq!(photos[0].title, photo.title);
+        assert_eq!(photos[0].camera_model, photo.camera_model);
+    }
+}
@@ -0,0 +1,18 @@
+//! (De)serialize a `PathBuf` as a plain `TEXT` string, for fields that need to round-trip through
+//! `serde_rusqlite`'s row (de)serialization but aren't natively serde-friendly for SQLite.
+//!
+//! `Photo::path` uses this via `#[serde(with = "crate::db::path_as_text")]`.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<S: Serializer>(path: &PathBuf, serializer: S) -> Result<S::Ok, S::Error> {
+    path.to_str()
+        .ok_or_else(|| serde::ser::Error::custom("path is not valid UTF-8"))?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+    String::deserialize(deserializer).map(PathBuf::from)
+}
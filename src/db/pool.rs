@@ -0,0 +1,45 @@
+//! Connection pooling for the photos database.
+//!
+//! A single shared `Mutex<Connection>` serializes every database access and, since it's held
+//! across the blocking rusqlite calls, stalls Rocket's async worker threads while SQLite does
+//! synchronous I/O. Pooling connections with r2d2 lets independent reads run concurrently, and
+//! callers run their query bodies inside `tokio::task::spawn_blocking` so the blocking work never
+//! runs directly on an async worker thread.
+
+use r2d2::CustomizeConnection;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::Connection;
+
+use crate::config::Config;
+
+use super::functions;
+
+/// A pool of connections to the photos database.
+pub type DatabaseConnectionPool = r2d2::Pool<SqliteConnectionManager>;
+
+/// Build the connection pool used to access the photos database, sized from `config`.
+///
+/// Every connection handed out by the pool has WAL journaling enabled and a busy timeout
+/// configured, so concurrent writers don't immediately fail with `SQLITE_BUSY`.
+pub fn build_pool(config: &Config) -> Result<DatabaseConnectionPool, r2d2::Error> {
+    let manager = SqliteConnectionManager::file(&config.DATABASE_PATH);
+
+    r2d2::Pool::builder()
+        .max_size(config.DATABASE_POOL_SIZE)
+        .connection_customizer(Box::new(ConnectionCustomizer))
+        .build(manager)
+}
+
+/// Applies the pragmas every pooled connection needs (WAL mode, busy timeout) and registers the
+/// application-defined SQL functions, as soon as r2d2 creates the connection.
+#[derive(Debug)]
+struct ConnectionCustomizer;
+
+impl CustomizeConnection<Connection, rusqlite::Error> for ConnectionCustomizer {
+    fn on_acquire(&self, db_conn: &mut Connection) -> Result<(), rusqlite::Error> {
+        db_conn.pragma_update(None, "journal_mode", "WAL")?;
+        db_conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        functions::register(db_conn)?;
+        Ok(())
+    }
+}
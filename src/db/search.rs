@@ -0,0 +1,95 @@
+//! Full-text search over photo metadata, backed by the `photo_fts` FTS5 virtual table created
+//! in the `fts5_search` migration.
+
+use crate::{Error, photos::Photo};
+
+use super::{row_to_photo, DatabaseConnectionPool};
+
+/// Search for photos whose title, place, camera model or lens mode match `query`.
+///
+/// `query` is a plain, space-separated list of search terms (an optional trailing `*` on a term
+/// requests a prefix match, e.g. `"canon* leica"`); terms are quoted as FTS5 string literals
+/// before being sent to SQLite so stray FTS5 operators in user input can't produce a MATCH syntax
+/// error. Results are ordered by FTS5's relevance `rank`.
+pub async fn search_photos(db_pool: &DatabaseConnectionPool, query: &str) -> Result<Vec<Photo>, Error> {
+    // FTS5 treats an empty MATCH expression as a syntax error, so a blank search box (no terms)
+    // can't be turned into a MATCH query at all; there's nothing to match against, so just return
+    // no results instead of surfacing a database error to the caller.
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let db_pool = db_pool.clone();
+    let query = sanitize_fts5_query(query);
+
+    tokio::task::spawn_blocking(move || {
+        let db_conn = db_pool.get().map_err(|e| Error::DatabasePoolError(e))?;
+
+        let sql = "SELECT photo.* FROM photo JOIN photo_fts ON photo.id = photo_fts.rowid WHERE photo_fts MATCH ? ORDER BY rank;";
+
+        let mut stmt = db_conn.prepare(sql)
+            .map_err(|e| Error::DatabaseError(e))?;
+
+        let photos = stmt.query_map(rusqlite::params![&query], |row|
+            row_to_photo(row)
+        )
+            .map_err(|e| Error::DatabaseError(e))?
+            .map(|x| x.unwrap())
+            .collect::<Vec<Photo>>();
+
+        Ok(photos)
+    }).await.unwrap()
+}
+
+/// Turn free-form user input into a safe FTS5 MATCH expression by quoting every term as a string
+/// literal, so characters like `"`, `-` or unmatched parentheses can't be interpreted as FTS5
+/// query syntax. A term ending in `*` keeps its prefix-match meaning.
+fn sanitize_fts5_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|term| {
+            let (term, is_prefix) = match term.strip_suffix('*') {
+                Some(prefix) => (prefix, true),
+                None => (term, false),
+            };
+            let escaped = term.replace('"', "\"\"");
+
+            if is_prefix {
+                format!("\"{}\"*", escaped)
+            } else {
+                format!("\"{}\"", escaped)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sanitize_fts5_query;
+
+    #[test]
+    fn quotes_each_term_as_a_phrase() {
+        assert_eq!(sanitize_fts5_query("canon leica"), "\"canon\" \"leica\"");
+    }
+
+    #[test]
+    fn keeps_the_prefix_operator_on_a_starred_term() {
+        assert_eq!(sanitize_fts5_query("canon* leica"), "\"canon\"* \"leica\"");
+    }
+
+    #[test]
+    fn escapes_embedded_double_quotes() {
+        assert_eq!(sanitize_fts5_query("18\"mm"), "\"18\"\"mm\"");
+    }
+
+    #[test]
+    fn defuses_fts5_operators_by_quoting_them_as_literal_terms() {
+        assert_eq!(sanitize_fts5_query("canon AND OR -leica"), "\"canon\" \"AND\" \"OR\" \"-leica\"");
+    }
+
+    #[test]
+    fn returns_an_empty_string_for_blank_input() {
+        assert_eq!(sanitize_fts5_query("   "), "");
+    }
+}
@@ -0,0 +1,24 @@
+use std::fmt;
+
+/// Errors that can occur while serving Niobium.
+#[derive(Debug)]
+pub enum Error {
+    /// A query against the photos database failed.
+    DatabaseError(rusqlite::Error),
+    /// Checking out a connection from the database connection pool failed.
+    DatabasePoolError(r2d2::Error),
+    /// Converting a database row to or from a Rust value via `serde_rusqlite` failed.
+    DeserializeError(serde_rusqlite::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::DatabaseError(error) => write!(f, "database error : {}", error),
+            Error::DatabasePoolError(error) => write!(f, "database pool error : {}", error),
+            Error::DeserializeError(error) => write!(f, "database (de)serialization error : {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
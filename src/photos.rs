@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::path_as_text;
+
+/// A single photo known to Niobium, as stored in the `photo` table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Photo {
+    pub id: i64,
+    pub filename: String,
+    #[serde(with = "path_as_text")]
+    pub path: PathBuf,
+    pub uid: String,
+    pub md5: String,
+    pub sort_order: i64,
+    pub hidden: bool,
+    pub metadata_parsed: bool,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub color: Option<String>,
+    pub title: Option<String>,
+    pub place: Option<String>,
+    pub date_taken: Option<String>,
+    pub camera_model: Option<String>,
+    pub lens_mode: Option<String>,
+    pub focal_length: Option<String>,
+    pub aperture: Option<String>,
+    pub exposure_time: Option<String>,
+    pub sensitivity: Option<String>,
+}